@@ -1,13 +1,22 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use std::process::Command;
-use std::time::Duration;
-use crate::config::Config;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::battery::{self, BatteryInfo};
+use crate::config::{Config, DeviceSelection};
+use crate::sparkline::{self, Sparkline};
 use cosmic::cosmic_config::{self, CosmicConfigEntry};
-use cosmic::iced::{window::Id, Subscription};
+use cosmic::iced::platform_specific::shell::commands::popup::{destroy_popup, get_popup};
+use cosmic::iced::{window::Id, Limits, Subscription};
 use cosmic::prelude::*;
 use cosmic::iced;
+use cosmic::widget;
 use futures_util::SinkExt;
+use futures_util::StreamExt;
+
+/// How much energy-rate history to keep around for the popup's sparkline.
+const HISTORY_WINDOW: Duration = Duration::from_secs(10 * 60);
 
 /// The application model stores app-specific state used to describe its interface and
 /// drive its logic.
@@ -19,18 +28,58 @@ pub struct AppModel {
     popup: Option<Id>,
     /// Configuration data that persists between application runs.
     config: Config,
+    /// Handle used to persist [`Config`] edits made from the tariff settings view.
+    config_handler: Option<cosmic_config::Config>,
     energy_rate: String,
+    /// Latest snapshot of every watched device, keyed by D-Bus object path.
+    devices: BTreeMap<String, BatteryInfo>,
+    /// Recent aggregate energy-rate samples, oldest first, trimmed to [`HISTORY_WINDOW`].
+    history: VecDeque<(Instant, f64)>,
+    /// Cumulative energy consumed this session, in watt-hours.
+    session_energy_wh: f64,
+    /// Timestamp of the last energy-rate sample, used to integrate `session_energy_wh`.
+    last_sample: Option<Instant>,
+    /// Whether the tariff settings form is expanded in the popup.
+    show_settings: bool,
+    /// Raw text of the price-per-kWh field, kept separately so partial input
+    /// (e.g. `"."`) isn't lost while it fails to parse.
+    price_input: String,
+    currency_input: String,
+    /// Whether we've already fired a high-power-draw notification for the
+    /// threshold crossing currently in effect; reset once the rate drops back down.
+    notified_high_wattage: bool,
+    /// Whether we've already fired a low-battery notification for the crossing
+    /// currently in effect; reset once charge rises back above the threshold.
+    notified_low_battery: bool,
+    high_wattage_input: String,
+    low_battery_input: String,
+    /// Raw text of the device path field, kept even while `device_selection`
+    /// isn't `Device(_)` so switching modes doesn't lose what was typed.
+    device_path_input: String,
 }
 
 /// Messages emitted by the application and its widgets.
 #[derive(Debug, Clone)]
 pub enum Message {
     PopupClosed(Id),
-    SubscriptionChannel,
+    TogglePopup,
+    ToggleSettings,
     UpdateConfig(Config),
-    UpdateEnergyRate,
+    UpdateDevice(String, BatteryInfo),
+    SetPricePerKwh(String),
+    SetCurrencySymbol(String),
+    SetNotificationsEnabled(bool),
+    SetHighWattageThreshold(String),
+    SetLowBatteryThreshold(String),
+    SetDeviceSelectionMode(usize),
+    SetDevicePath(String),
+    NotificationSent,
 }
 
+/// Labels for the dropdown in [`AppModel::view_window`], in the same order
+/// [`device_selection_mode_index`] maps [`DeviceSelection`] variants to.
+const DEVICE_SELECTION_MODES: [&str; 3] = ["Sum all devices", "Primary device only", "Specific device"];
+
 /// Create a COSMIC application from the app model
 impl cosmic::Application for AppModel {
     /// The async executor that will be used to run your application's commands.
@@ -58,19 +107,37 @@ impl cosmic::Application for AppModel {
         core: cosmic::Core,
         _flags: Self::Flags,
     ) -> (Self, Task<cosmic::Action<Self::Message>>) {
+        let config_handler = cosmic_config::Config::new(Self::APP_ID, Config::VERSION).ok();
+        let config: Config = config_handler
+            .as_ref()
+            .map(|context| Config::get_entry(context).unwrap_or_else(|(_errors, config)| {
+                // for why in errors {
+                //     tracing::error!(%why, "error loading app config");
+                // }
+
+                config
+            }))
+            .unwrap_or_default();
+
+        let devices: BTreeMap<String, BatteryInfo> = battery::poll(battery::DEVICE_PATH)
+            .map(|info| BTreeMap::from([(battery::DEVICE_PATH.to_string(), info)]))
+            .unwrap_or_default();
+
         // Construct the app model with the runtime's core.
         let app = AppModel {
             core,
-            config: cosmic_config::Config::new(Self::APP_ID, Config::VERSION)
-                .map(|context| Config::get_entry(&context).unwrap_or_else(|(_errors, config)| {
-                    // for why in errors {
-                    //     tracing::error!(%why, "error loading app config");
-                    // }
-
-                    config
-                }))
-                .unwrap_or_default(),
-            energy_rate: get_energy_rate(),
+            energy_rate: format_energy_rate(aggregate(&config.device_selection, &devices), &config),
+            price_input: config.price_per_kwh.to_string(),
+            currency_input: config.currency_symbol.clone(),
+            high_wattage_input: config.high_wattage_threshold.to_string(),
+            low_battery_input: config.low_battery_threshold.to_string(),
+            device_path_input: match &config.device_selection {
+                DeviceSelection::Device(path) => path.clone(),
+                _ => String::new(),
+            },
+            config,
+            config_handler,
+            devices,
             ..Default::default()
         };
 
@@ -88,15 +155,47 @@ impl cosmic::Application for AppModel {
     /// activated by selectively appending to the subscription batch, and will
     /// continue to execute for the duration that they remain in the batch.
     fn subscription(&self) -> Subscription<Self::Message> {
-        struct MySubscription;
+        struct BatterySubscription;
+
+        let device_selection = self.config.device_selection.clone();
 
         Subscription::batch(vec![
-            // Create a subscription which emits updates through a channel.
+            // Watches every selected UPower device over D-Bus for changes, falling
+            // back to polling `upower` on the command line if a device's bus
+            // connection fails.
+            //
+            // `device_selection` is folded into the subscription id so that changing
+            // it (sum all / primary / a specific device) tears down and restarts this
+            // stream with the newly resolved device paths, rather than leaving the
+            // original selection running until the applet restarts.
             Subscription::run_with_id(
-                std::any::TypeId::of::<MySubscription>(),
-                cosmic::iced::stream::channel(4, move |mut channel| async move {
-                    _ = channel.send(Message::SubscriptionChannel).await;
+                (std::any::TypeId::of::<BatterySubscription>(), device_selection.clone()),
+                cosmic::iced::stream::channel(16, move |channel| async move {
+                    let paths = battery::resolve_devices(&device_selection)
+                        .await
+                        .unwrap_or_else(|why| {
+                            eprintln!("falling back to default battery device: {why}");
+                            vec![battery::DEVICE_PATH.to_string()]
+                        });
+
+                    let watchers = paths.into_iter().map(|path| {
+                        let mut channel = channel.clone();
+                        async move {
+                            match battery::watch(&path).await {
+                                Ok(mut updates) => {
+                                    while let Some(info) = updates.next().await {
+                                        _ = channel.send(Message::UpdateDevice(path.clone(), info)).await;
+                                    }
+                                }
+                                Err(why) => {
+                                    eprintln!("falling back to polling upower for {path}: {why}");
+                                    poll_device(path, &mut channel).await;
+                                }
+                            }
+                        }
+                    });
 
+                    futures_util::future::join_all(watchers).await;
                     futures_util::future::pending().await
                 }),
             ),
@@ -110,7 +209,6 @@ impl cosmic::Application for AppModel {
 
                     Message::UpdateConfig(update.config)
                 }),
-            iced::time::every(Duration::from_secs(1)).map(|_| Message::UpdateEnergyRate),
         ])
     }
 
@@ -121,20 +219,128 @@ impl cosmic::Application for AppModel {
     /// tasks are finished.
     fn update(&mut self, message: Self::Message) -> Task<cosmic::Action<Self::Message>> {
         match message {
-            Message::SubscriptionChannel => {
-                // For example purposes only.
-            }
             Message::UpdateConfig(config) => {
+                self.price_input = config.price_per_kwh.to_string();
+                self.currency_input = config.currency_symbol.clone();
+                self.high_wattage_input = config.high_wattage_threshold.to_string();
+                self.low_battery_input = config.low_battery_threshold.to_string();
+                if let DeviceSelection::Device(path) = &config.device_selection {
+                    self.device_path_input = path.clone();
+                }
+                if config.device_selection != self.config.device_selection {
+                    self.devices.clear();
+                }
                 self.config = config;
+                self.energy_rate = format_energy_rate(
+                    aggregate(&self.config.device_selection, &self.devices),
+                    &self.config,
+                );
+            }
+            Message::TogglePopup => {
+                return if let Some(popup) = self.popup.take() {
+                    destroy_popup(popup)
+                } else {
+                    let new_id = Id::unique();
+                    self.popup = Some(new_id);
+
+                    let mut popup_settings = self.core.applet.get_popup_settings(
+                        self.core.main_window_id().unwrap(),
+                        new_id,
+                        None,
+                        None,
+                        None,
+                    );
+                    popup_settings.positioner.size_limits = Limits::NONE
+                        .max_width(340.0)
+                        .min_width(280.0)
+                        .min_height(200.0)
+                        .max_height(640.0);
+
+                    get_popup(popup_settings)
+                };
+            }
+            Message::ToggleSettings => {
+                self.show_settings = !self.show_settings;
             }
             Message::PopupClosed(id) => {
                 if self.popup.as_ref() == Some(&id) {
                     self.popup = None;
                 }
             },
-            Message::UpdateEnergyRate => {
-                let new_energy_rate = get_energy_rate();
-                self.energy_rate = new_energy_rate;
+            Message::SetPricePerKwh(text) => {
+                if let Ok(price) = text.parse::<f64>() {
+                    self.update_config(|config| config.price_per_kwh = price);
+                    self.energy_rate = format_energy_rate(
+                        aggregate(&self.config.device_selection, &self.devices),
+                        &self.config,
+                    );
+                }
+                self.price_input = text;
+            }
+            Message::SetCurrencySymbol(text) => {
+                self.update_config(|config| config.currency_symbol = text.clone());
+                self.currency_input = text;
+            }
+            Message::SetNotificationsEnabled(enabled) => {
+                self.update_config(|config| config.notifications_enabled = enabled);
+            }
+            Message::SetHighWattageThreshold(text) => {
+                if let Ok(threshold) = text.parse::<f64>() {
+                    self.update_config(|config| config.high_wattage_threshold = threshold);
+                }
+                self.high_wattage_input = text;
+            }
+            Message::SetLowBatteryThreshold(text) => {
+                if let Ok(threshold) = text.parse::<f64>() {
+                    self.update_config(|config| config.low_battery_threshold = threshold);
+                }
+                self.low_battery_input = text;
+            }
+            Message::SetDeviceSelectionMode(index) => {
+                let selection = match index {
+                    0 => DeviceSelection::SumAll,
+                    1 => DeviceSelection::PrimaryOnly,
+                    _ => DeviceSelection::Device(self.device_path_input.clone()),
+                };
+                self.update_config(|config| config.device_selection = selection.clone());
+            }
+            Message::SetDevicePath(path) => {
+                if matches!(self.config.device_selection, DeviceSelection::Device(_)) {
+                    self.update_config(|config| config.device_selection = DeviceSelection::Device(path.clone()));
+                }
+                self.device_path_input = path;
+            }
+            Message::NotificationSent => {
+                // Nothing to do; errors are already logged where the notification is sent.
+            }
+            Message::UpdateDevice(path, info) => {
+                self.devices.insert(path, info);
+
+                let aggregate_rate = aggregate(&self.config.device_selection, &self.devices);
+                self.energy_rate = format_energy_rate(aggregate_rate, &self.config);
+
+                if let Some(rate) = aggregate_rate {
+                    let now = Instant::now();
+                    if let Some(last) = self.last_sample {
+                        let hours = now.duration_since(last).as_secs_f64() / 3600.0;
+                        self.session_energy_wh += rate * hours;
+                    }
+                    self.last_sample = Some(now);
+
+                    self.history.push_back((now, rate));
+                    while let Some((oldest, _)) = self.history.front() {
+                        if now.duration_since(*oldest) > HISTORY_WINDOW {
+                            self.history.pop_front();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+
+                let mut tasks = Vec::new();
+                tasks.extend(self.check_wattage_threshold(aggregate_rate));
+                tasks.extend(self.check_battery_threshold());
+                return Task::batch(tasks);
             }
         }
         Task::none()
@@ -148,8 +354,118 @@ impl cosmic::Application for AppModel {
     fn view(&self) -> Element<'_, Self::Message> {
         let text = self.core.applet.text(&self.energy_rate).size(15.0);
         let padding = iced::Padding { top: 1.0 , right: 5.0, bottom: 1.0, left: 5.0 };
-        let container: cosmic::widget::Container<Message, Theme>  = cosmic::widget::container(text).padding(padding);
-        self.core.applet.autosize_window(container).into()
+        let container: cosmic::widget::Container<Message, Theme> = cosmic::widget::container(text).padding(padding);
+        let button = cosmic::widget::button::custom(container)
+            .class(cosmic::theme::Button::AppletIcon)
+            .on_press(Message::TogglePopup);
+        self.core.applet.autosize_window(button).into()
+    }
+
+    /// Describes the popup opened from the panel button: a detailed status column
+    /// per watched device, parsed from the same UPower devices the panel label reads from.
+    fn view_window(&self, _id: Id) -> Element<'_, Self::Message> {
+        let mut content = widget::column().spacing(8).padding(16);
+
+        if self.devices.is_empty() {
+            content = content.push(widget::text("No battery information available"));
+        }
+
+        for (path, battery) in &self.devices {
+            content = content.push(widget::text::title4(device_label(path)));
+            content = content.push(status_row("Energy rate", format!("{:.2} W", battery.energy_rate)));
+            if self.config.price_per_kwh > 0.0 {
+                content = content.push(status_row("Running cost", format_cost(battery.energy_rate, &self.config)));
+            }
+            content = content.push(status_row("Charge", format!("{:.0}%", battery.percentage)));
+            content = content.push(status_row("State", battery.state.to_string()));
+
+            match battery.state {
+                battery::BatteryState::Discharging if !battery.time_to_empty.is_zero() => {
+                    content = content.push(status_row("Time to empty", format_duration(battery.time_to_empty)));
+                }
+                battery::BatteryState::Charging if !battery.time_to_full.is_zero() => {
+                    content = content.push(status_row("Time to full", format_duration(battery.time_to_full)));
+                }
+                _ => {}
+            }
+
+            if let Some(health) = battery.health_percent() {
+                content = content.push(status_row("Health", format!("{health:.0}%")));
+            }
+            content = content.push(status_row("Voltage", format!("{:.2} V", battery.voltage)));
+        }
+
+        if let Some((min, max, avg)) = sparkline::stats(&self.history) {
+            content = content.push(widget::text::title4("Power draw"));
+            content = content.push(Sparkline::new(&self.history, HISTORY_WINDOW).view());
+            content = content.push(status_row(
+                "Min / avg / max",
+                format!("{min:.1} / {avg:.1} / {max:.1} W"),
+            ));
+        }
+
+        if self.config.price_per_kwh > 0.0 {
+            content = content.push(status_row(
+                "Session cost",
+                format!(
+                    "{}{:.3} ({:.1} Wh)",
+                    self.config.currency_symbol,
+                    self.session_energy_wh / 1000.0 * self.config.price_per_kwh,
+                    self.session_energy_wh
+                ),
+            ));
+        }
+
+        content = content.push(
+            widget::button::text(if self.show_settings { "Hide tariff settings" } else { "Edit tariff settings" })
+                .on_press(Message::ToggleSettings),
+        );
+
+        if self.show_settings {
+            content = content.push(
+                widget::row()
+                    .push(widget::text("Devices").width(iced::Length::Fill))
+                    .push(widget::dropdown(
+                        &DEVICE_SELECTION_MODES,
+                        Some(device_selection_mode_index(&self.config.device_selection)),
+                        Message::SetDeviceSelectionMode,
+                    )),
+            );
+            if matches!(self.config.device_selection, DeviceSelection::Device(_)) {
+                content = content.push(status_row_input(
+                    "Device path",
+                    &self.device_path_input,
+                    Message::SetDevicePath,
+                ));
+            }
+            content = content.push(status_row_input(
+                "Price per kWh",
+                &self.price_input,
+                Message::SetPricePerKwh,
+            ));
+            content = content.push(status_row_input(
+                "Currency symbol",
+                &self.currency_input,
+                Message::SetCurrencySymbol,
+            ));
+            content = content.push(
+                widget::row()
+                    .push(widget::text("Notify on thresholds").width(iced::Length::Fill))
+                    .push(widget::toggler(self.config.notifications_enabled).on_toggle(Message::SetNotificationsEnabled)),
+            );
+            content = content.push(status_row_input(
+                "High draw threshold (W)",
+                &self.high_wattage_input,
+                Message::SetHighWattageThreshold,
+            ));
+            content = content.push(status_row_input(
+                "Low battery threshold (%)",
+                &self.low_battery_input,
+                Message::SetLowBatteryThreshold,
+            ));
+        }
+
+        self.core.applet.popup_container(content).into()
     }
 
     fn style(&self) -> Option<cosmic::iced_runtime::Appearance> {
@@ -157,24 +473,212 @@ impl cosmic::Application for AppModel {
     }
 }
 
-fn get_energy_rate() -> String {
-    let output = Command::new("upower")
-        .arg("-i")
-        .arg("/org/freedesktop/UPower/devices/battery_BAT0")
-        .output()
-        .expect("Failed to execute upower");
+impl AppModel {
+    /// Applies `f` to the in-memory config, then persists the result through
+    /// `cosmic_config` so other instances pick it up via `watch_config`.
+    fn update_config(&mut self, f: impl FnOnce(&mut Config)) {
+        let previous_selection = self.config.device_selection.clone();
+        f(&mut self.config);
+
+        // A new selection restarts the device subscription on its own paths;
+        // drop snapshots of devices that are no longer watched.
+        if self.config.device_selection != previous_selection {
+            self.devices.clear();
+        }
 
-    if output.status.success() {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        for line in stdout.lines() {
-            if line.trim_start().starts_with("energy-rate:") {
-                return format!("Energy Rate: {}",line.replace("energy-rate:", "").trim());
+        if let Some(handler) = &self.config_handler {
+            if let Err(why) = self.config.write_entry(handler) {
+                eprintln!("failed to persist config: {why}");
             }
         }
+    }
+
+    /// Edge-triggers a notification the first time `rate` crosses above the
+    /// configured wattage threshold, debouncing further updates until it drops
+    /// back down.
+    fn check_wattage_threshold(&mut self, rate: Option<f64>) -> Option<Task<cosmic::Action<Message>>> {
+        if !self.config.notifications_enabled || self.config.high_wattage_threshold <= 0.0 {
+            self.notified_high_wattage = false;
+            return None;
+        }
+
+        // `energy_rate` is an unsigned magnitude; only a net discharge should
+        // ever be read as "drawing power" for this alert.
+        let discharging = is_discharging(&self.config.device_selection, &self.devices);
+        let above = discharging && rate.is_some_and(|rate| rate > self.config.high_wattage_threshold);
+        if !above {
+            self.notified_high_wattage = false;
+            return None;
+        }
+        if self.notified_high_wattage {
+            return None;
+        }
+
+        self.notified_high_wattage = true;
+        let threshold = self.config.high_wattage_threshold;
+        Some(notify_task(
+            "High power draw".into(),
+            format!("Drawing {:.1} W, above your {threshold:.1} W threshold", rate.unwrap_or_default()),
+        ))
+    }
+
+    /// Edge-triggers a notification the first time any watched device's charge
+    /// crosses below the configured percentage, debouncing further updates
+    /// until it rises back above it.
+    fn check_battery_threshold(&mut self) -> Option<Task<cosmic::Action<Message>>> {
+        if !self.config.notifications_enabled || self.config.low_battery_threshold <= 0.0 {
+            self.notified_low_battery = false;
+            return None;
+        }
+
+        let lowest = self.devices.values().map(|info| info.percentage).reduce(f64::min)?;
+        let below = lowest < self.config.low_battery_threshold;
+        if !below {
+            self.notified_low_battery = false;
+            return None;
+        }
+        if self.notified_low_battery {
+            return None;
+        }
+
+        self.notified_low_battery = true;
+        let threshold = self.config.low_battery_threshold;
+        Some(notify_task(
+            "Low battery".into(),
+            format!("Battery at {lowest:.0}%, below your {threshold:.0}% threshold"),
+        ))
+    }
+}
+
+/// Wraps a fire-and-forget desktop notification in a [`Task`], logging (rather
+/// than surfacing) any failure to send it.
+fn notify_task(summary: String, body: String) -> Task<cosmic::Action<Message>> {
+    Task::perform(
+        async move {
+            if let Err(why) = crate::notifications::notify(&summary, &body).await {
+                eprintln!("failed to send notification: {why}");
+            }
+        },
+        |()| cosmic::Action::App(Message::NotificationSent),
+    )
+}
+
+/// Derives a short label from a UPower device's object path, e.g.
+/// `/org/freedesktop/UPower/devices/battery_BAT0` -> `BAT0`.
+fn device_label(path: &str) -> String {
+    path.rsplit('/')
+        .next()
+        .and_then(|segment| segment.split('_').next_back())
+        .map(str::to_string)
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Combines every watched device's energy rate according to `selection`.
+fn aggregate(selection: &DeviceSelection, devices: &BTreeMap<String, BatteryInfo>) -> Option<f64> {
+    match selection {
+        DeviceSelection::Device(path) => devices.get(path).map(|info| info.energy_rate),
+        DeviceSelection::PrimaryOnly => devices.values().next().map(|info| info.energy_rate),
+        DeviceSelection::SumAll => (!devices.is_empty())
+            .then(|| devices.values().map(|info| info.energy_rate).sum()),
+    }
+}
+
+/// Whether the device(s) `selection` resolves to should be considered
+/// discharging, for gating the high-power-draw notification.
+fn is_discharging(selection: &DeviceSelection, devices: &BTreeMap<String, BatteryInfo>) -> bool {
+    match selection {
+        DeviceSelection::Device(path) => devices
+            .get(path)
+            .is_some_and(|info| info.state == battery::BatteryState::Discharging),
+        DeviceSelection::PrimaryOnly => devices
+            .values()
+            .next()
+            .is_some_and(|info| info.state == battery::BatteryState::Discharging),
+        DeviceSelection::SumAll => devices
+            .values()
+            .any(|info| info.state == battery::BatteryState::Discharging),
+    }
+}
+
+/// Maps a [`DeviceSelection`] to its index into [`DEVICE_SELECTION_MODES`].
+fn device_selection_mode_index(selection: &DeviceSelection) -> usize {
+    match selection {
+        DeviceSelection::SumAll => 0,
+        DeviceSelection::PrimaryOnly => 1,
+        DeviceSelection::Device(_) => 2,
+    }
+}
+
+/// Lays out one label/value pair in the popup's status column.
+fn status_row(label: &str, value: impl Into<String>) -> Element<'static, Message> {
+    widget::row()
+        .push(widget::text(label.to_string()).width(iced::Length::Fill))
+        .push(widget::text(value.into()))
+        .into()
+}
+
+/// Lays out one label/text-input pair in the tariff settings form.
+fn status_row_input(
+    label: &str,
+    value: &str,
+    on_input: impl Fn(String) -> Message + 'static,
+) -> Element<'static, Message> {
+    widget::row()
+        .push(widget::text(label.to_string()).width(iced::Length::Fill))
+        .push(widget::text_input("", value).on_input(on_input))
+        .into()
+}
+
+fn format_duration(duration: std::time::Duration) -> String {
+    let total_minutes = duration.as_secs() / 60;
+    let hours = total_minutes / 60;
+    let minutes = total_minutes % 60;
+    if hours > 0 {
+        format!("{hours}h {minutes}m")
     } else {
-        eprintln!("Error: {}", String::from_utf8_lossy(&output.stderr));
-        return "Error".into();
+        format!("{minutes}m")
     }
+}
+
+/// Estimated running cost for drawing `rate` watts continuously for an hour.
+fn format_cost(rate: f64, config: &Config) -> String {
+    format!("{}{:.3}/hr", config.currency_symbol, rate / 1000.0 * config.price_per_kwh)
+}
 
-    "".into()
-}
\ No newline at end of file
+fn format_energy_rate(rate: Option<f64>, config: &Config) -> String {
+    match rate {
+        Some(rate) if config.price_per_kwh > 0.0 => {
+            format!("{rate:.2} W ({})", format_cost(rate, config))
+        }
+        Some(rate) => format!("Energy Rate: {rate:.2} W"),
+        None => "Error".into(),
+    }
+}
+
+/// Fallback path used when a device's UPower bus connection can't be established:
+/// polls `upower -i` on the command line once a second, same as before D-Bus
+/// support existed.
+async fn poll_device(path: String, channel: &mut futures_util::channel::mpsc::Sender<Message>) {
+    loop {
+        if let Some(info) = battery::poll(&path) {
+            _ = channel.send(Message::UpdateDevice(path.clone(), info)).await;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_label_strips_path_and_prefix() {
+        assert_eq!(device_label("/org/freedesktop/UPower/devices/battery_BAT0"), "BAT0");
+        assert_eq!(device_label("/org/freedesktop/UPower/devices/line_power_AC"), "AC");
+    }
+
+    #[test]
+    fn device_label_falls_back_to_whole_path_without_a_segment() {
+        assert_eq!(device_label("not-a-path"), "not-a-path");
+    }
+}
@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use cosmic::cosmic_config::{cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
+use serde::{Deserialize, Serialize};
+
+/// Which UPower device(s) the applet reads and how it combines them into the
+/// panel's single energy-rate label.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DeviceSelection {
+    /// Combine every discovered battery/UPS device into one total.
+    SumAll,
+    /// Use only the first discovered device.
+    PrimaryOnly,
+    /// Use a specific device, identified by its D-Bus object path.
+    Device(String),
+}
+
+impl Default for DeviceSelection {
+    fn default() -> Self {
+        Self::SumAll
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, CosmicConfigEntry, Serialize, Deserialize)]
+#[version = 1]
+pub struct Config {
+    pub device_selection: DeviceSelection,
+    /// Electricity price per kilowatt-hour, in the user's local currency.
+    pub price_per_kwh: f64,
+    /// Symbol prefixed to cost figures, e.g. `"$"` or `"€"`.
+    pub currency_symbol: String,
+    /// Whether threshold notifications are sent at all.
+    pub notifications_enabled: bool,
+    /// Notify when discharge rate exceeds this many watts. `<= 0.0` disables it.
+    pub high_wattage_threshold: f64,
+    /// Notify when charge drops below this percentage. `<= 0.0` disables it.
+    pub low_battery_threshold: f64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            device_selection: DeviceSelection::default(),
+            price_per_kwh: 0.0,
+            currency_symbol: "$".to_string(),
+            notifications_enabled: false,
+            high_wattage_threshold: 0.0,
+            low_battery_threshold: 15.0,
+        }
+    }
+}
@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Desktop notifications sent over the freedesktop `org.freedesktop.Notifications`
+//! D-Bus interface, used for threshold crossings the user has opted into.
+
+use std::collections::HashMap;
+
+use zbus::zvariant::Value;
+
+const APP_NAME: &str = "Energy Rate Applet";
+
+/// Sends a desktop notification with `summary`/`body`.
+pub async fn notify(summary: &str, body: &str) -> zbus::Result<()> {
+    let connection = zbus::Connection::session().await?;
+    let proxy = zbus::Proxy::new(
+        &connection,
+        "org.freedesktop.Notifications",
+        "/org/freedesktop/Notifications",
+        "org.freedesktop.Notifications",
+    )
+    .await?;
+
+    proxy
+        .call_method(
+            "Notify",
+            &(
+                APP_NAME,
+                0u32,
+                "battery-caution-symbolic",
+                summary,
+                body,
+                Vec::<&str>::new(),
+                HashMap::<&str, Value>::new(),
+                -1i32,
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! A minimal line/area graph for plotting recent energy-rate samples.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use cosmic::iced::mouse;
+use cosmic::iced::{Point, Rectangle};
+use cosmic::iced_core::Theme;
+use cosmic::widget::canvas::{self, Canvas, Frame, Geometry, Path, Stroke};
+use cosmic::Element;
+
+/// Renders `samples` (oldest first) over the trailing `window` as a filled line
+/// graph, auto-scaled to the largest value observed.
+pub struct Sparkline<'a> {
+    samples: &'a VecDeque<(Instant, f64)>,
+    window: Duration,
+}
+
+impl<'a> Sparkline<'a> {
+    pub fn new(samples: &'a VecDeque<(Instant, f64)>, window: Duration) -> Self {
+        Self { samples, window }
+    }
+
+    pub fn view<Message: 'a>(self) -> Element<'a, Message> {
+        Canvas::new(self).width(300.0).height(80.0).into()
+    }
+}
+
+impl<'a, Message> canvas::Program<Message> for Sparkline<'a> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &cosmic::iced_core::Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+
+        // Nothing meaningful to draw with fewer than two points.
+        if self.samples.len() < 2 {
+            return vec![frame.into_geometry()];
+        }
+
+        let max = self
+            .samples
+            .iter()
+            .map(|(_, watts)| *watts)
+            .fold(0.0_f64, f64::max);
+        let now = self.samples.back().map(|(t, _)| *t).unwrap_or_else(Instant::now);
+        let earliest = now.checked_sub(self.window).unwrap_or(now);
+        let span = self.window.as_secs_f32().max(1.0);
+
+        let x_for = |t: Instant| {
+            let elapsed = t.saturating_duration_since(earliest).as_secs_f32();
+            (elapsed / span) * bounds.width
+        };
+        let y_for = |watts: f64| {
+            if max <= 0.0 {
+                bounds.height
+            } else {
+                bounds.height - (watts / max) as f32 * bounds.height
+            }
+        };
+
+        let line = Path::new(|builder| {
+            let mut points = self.samples.iter().map(|(t, w)| Point::new(x_for(*t), y_for(*w)));
+            if let Some(first) = points.next() {
+                builder.move_to(first);
+                for point in points {
+                    builder.line_to(point);
+                }
+            }
+        });
+        frame.stroke(&line, Stroke::default().with_width(2.0));
+
+        let area = Path::new(|builder| {
+            builder.move_to(Point::new(0.0, bounds.height));
+            for (t, w) in self.samples {
+                builder.line_to(Point::new(x_for(*t), y_for(*w)));
+            }
+            builder.line_to(Point::new(bounds.width, bounds.height));
+            builder.close();
+        });
+        frame.fill(&area, cosmic::iced::Color::from_rgba(0.4, 0.6, 1.0, 0.2));
+
+        vec![frame.into_geometry()]
+    }
+}
+
+/// Minimum, maximum and average watts across `samples`, or `None` if empty.
+pub fn stats(samples: &VecDeque<(Instant, f64)>) -> Option<(f64, f64, f64)> {
+    if samples.is_empty() {
+        return None;
+    }
+
+    let mut min = f64::MAX;
+    let mut max = f64::MIN;
+    let mut sum = 0.0;
+    for (_, watts) in samples {
+        min = min.min(*watts);
+        max = max.max(*watts);
+        sum += watts;
+    }
+
+    Some((min, max, sum / samples.len() as f64))
+}